@@ -1,32 +1,56 @@
 use std::{
     borrow::Borrow,
-    collections::hash_map::{IntoIter, Iter},
-    hash::Hash,
+    cmp::{Ordering, Reverse},
+    collections::{
+        hash_map::{IntoIter, Iter},
+        BinaryHeap, HashMap,
+    },
+    hash::{BuildHasher, Hash},
     iter::Sum,
-    ops::{AddAssign, Index, IndexMut},
+    ops::{Add, AddAssign, Index, IndexMut, Sub, SubAssign},
 };
 
-use ahash::AHashMap;
+use ahash::RandomState;
 use num_traits::{One, Zero};
 
 /// Struct for counting hash-able objects or primitives
 ///
 /// Uses [`std::collections::HashMap`] underneath,
 /// also borrowing some of it's api
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Counter<T, C = usize>
+#[derive(Debug, Clone)]
+pub struct Counter<T, C = usize, S = RandomState>
 where
     T: Hash + Eq,
 {
-    map: AHashMap<T, C>,
+    map: HashMap<T, C, S>,
     zero: C,
 }
 
-impl<T, Q, C> Index<&'_ Q> for Counter<T, C>
+impl<T, C, S> PartialEq for Counter<T, C, S>
+where
+    T: Hash + Eq,
+    C: PartialEq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map && self.zero == other.zero
+    }
+}
+
+impl<T, C, S> Eq for Counter<T, C, S>
+where
+    T: Hash + Eq,
+    C: Eq,
+    S: BuildHasher,
+{
+}
+
+impl<T, Q, C, S> Index<&'_ Q> for Counter<T, C, S>
 where
     T: Hash + Eq + Borrow<Q>,
     Q: Hash + Eq,
     C: Zero,
+    S: BuildHasher,
 {
     type Output = C;
 
@@ -35,21 +59,23 @@ where
     }
 }
 
-impl<T, Q, C> IndexMut<&'_ Q> for Counter<T, C>
+impl<T, Q, C, S> IndexMut<&'_ Q> for Counter<T, C, S>
 where
     T: Hash + Eq + Borrow<Q>,
     Q: Hash + Eq + ToOwned<Owned = T>,
     C: Zero,
+    S: BuildHasher,
 {
     fn index_mut(&mut self, key: &'_ Q) -> &mut C {
         self.map.entry(key.to_owned()).or_insert_with(C::zero)
     }
 }
 
-impl<T, C> Default for Counter<T, C>
+impl<T, C, S> Default for Counter<T, C, S>
 where
     T: Hash + Eq,
     C: Default,
+    S: Default,
 {
     fn default() -> Self {
         Self {
@@ -59,46 +85,99 @@ where
     }
 }
 
-impl<T, C> Counter<T, C>
+impl<T, C, S> Counter<T, C, S>
 where
     T: Eq + Hash,
 {
     /// Get a reference to the underlying HashMap
-    pub fn get_map(&self) -> &AHashMap<T, C> {
+    pub fn get_map(&self) -> &HashMap<T, C, S> {
         &self.map
     }
 
     /// Consume the counter and return the underlying HashMap
-    pub fn into_map(self) -> AHashMap<T, C> {
+    pub fn into_map(self) -> HashMap<T, C, S> {
         self.map
     }
 
     /// Calculate sum of all counts.
-    pub fn total<'a, S>(&'a self) -> S
+    pub fn total<'a, N>(&'a self) -> N
     where
-        S: Sum<&'a C>,
+        N: Sum<&'a C>,
     {
         self.map.values().sum()
     }
 }
 
-impl<T, C> Counter<T, C>
+impl<T, C, S> Counter<T, C, S>
 where
     T: Eq + Hash,
     C: Zero,
+    S: BuildHasher + Default,
 {
     pub fn new() -> Self {
         Self {
-            map: AHashMap::new(),
+            map: HashMap::default(),
+            zero: C::zero(),
+        }
+    }
+
+    /// Create an empty counter with space preallocated for `capacity` distinct
+    /// keys, avoiding rehash churn while the first `capacity` keys are added.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: HashMap::with_capacity_and_hasher(capacity, S::default()),
+            zero: C::zero(),
+        }
+    }
+}
+
+impl<T, C, S> Counter<T, C, S>
+where
+    T: Eq + Hash,
+    C: Zero,
+    S: BuildHasher,
+{
+    /// Create an empty counter that hashes keys with `hash_builder`, letting
+    /// callers pick a fixed-seed or DoS-resistant hasher.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            map: HashMap::with_hasher(hash_builder),
             zero: C::zero(),
         }
     }
+
+    /// Create an empty counter with space preallocated for `capacity` distinct
+    /// keys, hashing them with `hash_builder`.
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self {
+            map: HashMap::with_capacity_and_hasher(capacity, hash_builder),
+            zero: C::zero(),
+        }
+    }
+}
+
+impl<T, C, S> Counter<T, C, S>
+where
+    T: Eq + Hash,
+    C: AddAssign + Zero + One,
+    S: BuildHasher,
+{
+    pub fn update<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable {
+            let entry = self.map.entry(item).or_insert_with(C::zero);
+            *entry += C::one();
+        }
+    }
 }
 
-impl<T, C> Counter<T, C>
+impl<T, C, S> Counter<T, C, S>
 where
     T: Eq + Hash,
     C: AddAssign + Zero + One,
+    S: BuildHasher + Default,
 {
     pub fn init<I>(iterable: I) -> Self
     where
@@ -108,19 +187,337 @@ where
         counter.update(iterable);
         counter
     }
+}
 
-    pub fn update<I>(&mut self, iterable: I)
+/// Heap entry ordered solely by its count, so the top-k heap never requires
+/// `T: Ord`.
+struct Ranked<'a, T, C> {
+    key: &'a T,
+    count: &'a C,
+}
+
+impl<T, C: Ord> PartialEq for Ranked<'_, T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.count == other.count
+    }
+}
+
+impl<T, C: Ord> Eq for Ranked<'_, T, C> {}
+
+impl<T, C: Ord> PartialOrd for Ranked<'_, T, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, C: Ord> Ord for Ranked<'_, T, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.count.cmp(other.count)
+    }
+}
+
+impl<T, C, S> Counter<T, C, S>
+where
+    T: Eq + Hash,
+    C: Ord,
+{
+    /// All entries, sorted by descending count.
+    ///
+    /// Ties between equal counts are resolved in an unspecified order, since
+    /// the underlying map has no stable iteration order; use
+    /// [`most_common_tiebreaker`](Self::most_common_tiebreaker) when a
+    /// deterministic order is required.
+    pub fn most_common(&self) -> Vec<(&T, &C)> {
+        let mut items: Vec<_> = self.map.iter().collect();
+        items.sort_unstable_by(|(_, a), (_, b)| b.cmp(a));
+        items
+    }
+
+    /// The `k` entries with the largest counts, sorted by descending count.
+    ///
+    /// Scans the map keeping a bounded min-heap of size `k`, so it runs in
+    /// `O(n log k)` time and `O(k)` space without fully sorting.
+    pub fn k_most_common_ordered(&self, k: usize) -> Vec<(&T, &C)> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        for (key, count) in &self.map {
+            heap.push(Reverse(Ranked { key, count }));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        let mut items = Vec::with_capacity(heap.len());
+        while let Some(Reverse(Ranked { key, count })) = heap.pop() {
+            items.push((key, count));
+        }
+        items.reverse();
+        items
+    }
+
+    /// All entries, sorted by descending count, breaking ties with
+    /// `tiebreaker` so the result is deterministic for equal counts.
+    pub fn most_common_tiebreaker<F>(&self, tiebreaker: F) -> Vec<(&T, &C)>
     where
-        I: IntoIterator<Item = T>,
+        F: Fn(&T, &T) -> Ordering,
     {
-        for item in iterable {
+        let mut items: Vec<_> = self.map.iter().collect();
+        items.sort_by(|(ak, av), (bk, bv)| bv.cmp(av).then_with(|| tiebreaker(ak, bk)));
+        items
+    }
+}
+
+impl<T, C, S> Counter<T, C, S>
+where
+    T: Clone + Eq + Hash,
+    C: Ord + Zero + Clone,
+    S: BuildHasher + Clone,
+{
+    /// Multiset union: for every key present in either counter, the
+    /// element-wise maximum of the two counts.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut map = self.map.clone();
+        for (key, count) in &other.map {
+            let entry = map.entry(key.clone()).or_insert_with(C::zero);
+            if *count > *entry {
+                *entry = count.clone();
+            }
+        }
+        Self {
+            map,
+            zero: C::zero(),
+        }
+    }
+
+    /// Multiset intersection: for every key present in both counters, the
+    /// element-wise minimum of the two counts. Keys whose minimum is not
+    /// greater than zero are dropped from the result.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut map = HashMap::with_hasher(self.map.hasher().clone());
+        for (key, count) in &self.map {
+            if let Some(other_count) = other.map.get(key) {
+                let min = count.min(other_count).clone();
+                if min > C::zero() {
+                    map.insert(key.clone(), min);
+                }
+            }
+        }
+        Self {
+            map,
+            zero: C::zero(),
+        }
+    }
+}
+
+impl<T, C, S> Counter<T, C, S>
+where
+    T: Eq + Hash,
+    C: SubAssign + Zero + PartialOrd,
+    S: BuildHasher,
+{
+    /// Subtract the counts in `other` from `self`, flooring at zero.
+    ///
+    /// Mirrors Python's `Counter.__sub__` (the `-` operator), not
+    /// `Counter.subtract`: counts never go negative, and any key whose count
+    /// reaches zero is removed. Use the [`Sub`]/[`SubAssign`] impls instead
+    /// when negative counts should be retained (e.g. for a signed `C`).
+    pub fn subtract(&mut self, other: Counter<T, C, S>) {
+        for (key, value) in other.map {
+            if let Some(entry) = self.map.get_mut(&key) {
+                if *entry > value {
+                    *entry -= value;
+                } else {
+                    self.map.remove(&key);
+                }
+            }
+        }
+    }
+}
+
+impl<T, C, S> AddAssign for Counter<T, C, S>
+where
+    T: Eq + Hash,
+    C: AddAssign + Zero,
+    S: BuildHasher,
+{
+    /// Merge the counts of `rhs` into `self` by summing per key.
+    fn add_assign(&mut self, rhs: Self) {
+        for (key, value) in rhs.map {
+            let entry = self.map.entry(key).or_insert_with(C::zero);
+            *entry += value;
+        }
+    }
+}
+
+impl<T, C, S> Add for Counter<T, C, S>
+where
+    T: Eq + Hash,
+    C: AddAssign + Zero,
+    S: BuildHasher,
+{
+    type Output = Counter<T, C, S>;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<T, C, S> SubAssign for Counter<T, C, S>
+where
+    T: Eq + Hash,
+    C: SubAssign + Zero,
+    S: BuildHasher,
+{
+    /// Subtract the counts of `rhs` from `self`, allowing negative results.
+    ///
+    /// # Panics
+    ///
+    /// This is scoped to a signed `C`. On an unsigned `C` (such as the default
+    /// `usize`) any count that would drop below zero overflows — a panic in
+    /// debug builds, a wrapping subtraction in release. Use the saturating
+    /// [`subtract`](Self::subtract) method for unsigned counts.
+    fn sub_assign(&mut self, rhs: Self) {
+        for (key, value) in rhs.map {
+            let entry = self.map.entry(key).or_insert_with(C::zero);
+            *entry -= value;
+        }
+    }
+}
+
+impl<T, C, S> Sub for Counter<T, C, S>
+where
+    T: Eq + Hash,
+    C: SubAssign + Zero,
+    S: BuildHasher,
+{
+    type Output = Counter<T, C, S>;
+
+    /// # Panics
+    ///
+    /// See [`SubAssign`]: on an unsigned `C` a result below zero overflows.
+    /// Use the saturating [`subtract`](Self::subtract) method instead.
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<I, T, C, S> AddAssign<I> for Counter<T, C, S>
+where
+    I: IntoIterator<Item = T>,
+    T: Eq + Hash,
+    C: AddAssign + Zero + One,
+    S: BuildHasher,
+{
+    /// Add every item of `rhs` to the counter, incrementing by one each.
+    fn add_assign(&mut self, rhs: I) {
+        self.update(rhs);
+    }
+}
+
+impl<I, T, C, S> Add<I> for Counter<T, C, S>
+where
+    I: IntoIterator<Item = T>,
+    T: Eq + Hash,
+    C: AddAssign + Zero + One,
+    S: BuildHasher,
+{
+    type Output = Counter<T, C, S>;
+
+    fn add(mut self, rhs: I) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<I, T, C, S> SubAssign<I> for Counter<T, C, S>
+where
+    I: IntoIterator<Item = T>,
+    T: Eq + Hash,
+    C: SubAssign + Zero + One,
+    S: BuildHasher,
+{
+    /// Subtract every item of `rhs` from the counter, decrementing by one
+    /// each and allowing negative results.
+    ///
+    /// # Panics
+    ///
+    /// This is scoped to a signed `C`. On an unsigned `C` (such as the default
+    /// `usize`) decrementing a key at zero — including a key present only in
+    /// `rhs` — overflows: a panic in debug builds, a wrapping subtraction in
+    /// release. Use the saturating [`subtract`](Self::subtract) method for
+    /// unsigned counts.
+    fn sub_assign(&mut self, rhs: I) {
+        for item in rhs {
             let entry = self.map.entry(item).or_insert_with(C::zero);
-            *entry += C::one();
+            *entry -= C::one();
+        }
+    }
+}
+
+impl<I, T, C, S> Sub<I> for Counter<T, C, S>
+where
+    I: IntoIterator<Item = T>,
+    T: Eq + Hash,
+    C: SubAssign + Zero + One,
+    S: BuildHasher,
+{
+    type Output = Counter<T, C, S>;
+
+    /// # Panics
+    ///
+    /// See [`SubAssign`]: on an unsigned `C` decrementing a key at zero
+    /// overflows. Use the saturating [`subtract`](Self::subtract) method
+    /// instead.
+    fn sub(mut self, rhs: I) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<T, C, S> FromIterator<T> for Counter<T, C, S>
+where
+    T: Eq + Hash,
+    C: AddAssign + Zero + One,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::init(iter)
+    }
+}
+
+impl<T, C, S> Extend<T> for Counter<T, C, S>
+where
+    T: Eq + Hash,
+    C: AddAssign + Zero + One,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.update(iter);
+    }
+}
+
+impl<T, C, S> Extend<(T, C)> for Counter<T, C, S>
+where
+    T: Eq + Hash,
+    C: AddAssign + Zero,
+    S: BuildHasher,
+{
+    /// Grow the counter with pre-counted `(item, count)` pairs, adding each
+    /// supplied count to the running total for that key.
+    fn extend<I: IntoIterator<Item = (T, C)>>(&mut self, iter: I) {
+        for (item, count) in iter {
+            let entry = self.map.entry(item).or_insert_with(C::zero);
+            *entry += count;
         }
     }
 }
 
-impl<'a, T, C> IntoIterator for &'a Counter<T, C>
+impl<'a, T, C, S> IntoIterator for &'a Counter<T, C, S>
 where
     T: Eq + Hash,
 {
@@ -132,7 +529,7 @@ where
     }
 }
 
-impl<T, C> IntoIterator for Counter<T, C>
+impl<T, C, S> IntoIterator for Counter<T, C, S>
 where
     T: Eq + Hash,
 {
@@ -143,3 +540,107 @@ where
         self.map.into_iter()
     }
 }
+
+#[cfg(feature = "serde")]
+impl<T, C, S> serde::Serialize for Counter<T, C, S>
+where
+    T: Eq + Hash + serde::Serialize,
+    C: serde::Serialize,
+    S: BuildHasher,
+{
+    /// Serialize as a plain map of key → count. The `zero` field is not
+    /// stored; it is reconstructed on deserialize.
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: serde::Serializer,
+    {
+        self.map.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, C, S> serde::Deserialize<'de> for Counter<T, C, S>
+where
+    T: Eq + Hash + serde::Deserialize<'de>,
+    C: Zero + serde::Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let map = HashMap::<T, C, S>::deserialize(deserializer)?;
+        Ok(Self {
+            map,
+            zero: C::zero(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Counter;
+
+    #[test]
+    fn subtract_removes_key_when_counts_equal() {
+        let mut a: Counter<char> = "aab".chars().collect();
+        let b: Counter<char> = "aa".chars().collect();
+        a.subtract(b);
+        assert_eq!(a.get_map().get(&'a'), None);
+        assert_eq!(a[&'b'], 1);
+    }
+
+    #[test]
+    fn subtract_ignores_keys_only_in_other() {
+        let mut a: Counter<char> = "a".chars().collect();
+        let b: Counter<char> = "zz".chars().collect();
+        a.subtract(b);
+        assert_eq!(a[&'a'], 1);
+        assert_eq!(a.get_map().get(&'z'), None);
+    }
+
+    #[test]
+    fn subtract_floors_at_zero_for_unsigned() {
+        let mut a: Counter<char> = "aaa".chars().collect();
+        let b: Counter<char> = "aaaaa".chars().collect();
+        a.subtract(b);
+        assert_eq!(a.get_map().get(&'a'), None);
+    }
+
+    #[test]
+    fn sub_retains_negative_for_signed() {
+        let a: Counter<char, i32> = "a".chars().collect();
+        let b: Counter<char, i32> = "aaa".chars().collect();
+        let diff = a - b;
+        assert_eq!(diff[&'a'], -2);
+    }
+
+    #[test]
+    fn k_most_common_ordered_is_descending() {
+        let c: Counter<char> = "aaabbc".chars().collect();
+        let top = c.k_most_common_ordered(2);
+        assert_eq!(top.len(), 2);
+        assert_eq!((*top[0].0, *top[0].1), ('a', 3));
+        assert_eq!((*top[1].0, *top[1].1), ('b', 2));
+    }
+
+    #[test]
+    fn k_most_common_ordered_counts_descending_with_ties() {
+        let c: Counter<char> = "aaabc".chars().collect();
+        let top = c.k_most_common_ordered(3);
+        let counts: Vec<usize> = top.iter().map(|(_, &n)| n).collect();
+        assert_eq!(counts, vec![3, 1, 1]);
+    }
+
+    #[test]
+    fn k_most_common_ordered_zero_is_empty() {
+        let c: Counter<char> = "abc".chars().collect();
+        assert!(c.k_most_common_ordered(0).is_empty());
+    }
+
+    #[test]
+    fn k_most_common_ordered_k_exceeds_len() {
+        let c: Counter<char> = "ab".chars().collect();
+        assert_eq!(c.k_most_common_ordered(10).len(), 2);
+    }
+}